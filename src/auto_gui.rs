@@ -1,4 +1,5 @@
 use crate::assets::Assets;
+use crate::screen::Screen;
 use image::{codecs::png::PngEncoder, ColorType, DynamicImage, GenericImageView, ImageEncoder};
 use std::error::Error;
 
@@ -12,6 +13,14 @@ pub struct AutoGui {
     debug: bool,
 }
 
+/// Where `find_image_on_screen` should source the target image from.
+pub enum ScreenSource<'a> {
+    /// An image embedded in `Assets`, addressed by path (e.g. `"screen.png"`).
+    Asset(&'a str),
+    /// A frame grabbed live from the display via `Screen`.
+    Capture,
+}
+
 pub struct FindImageRegion {
     left: u32,
     top: u32,
@@ -93,7 +102,7 @@ impl AutoGui {
         )
     }
 
-    fn _to_auto_gui_region(region: Option<FindImageRegion>) -> Option<(u32, u32, u32, u32)> {
+    fn _to_auto_gui_region(region: &Option<FindImageRegion>) -> Option<(u32, u32, u32, u32)> {
         let Some(region) = region else {
             return None;
         };
@@ -114,6 +123,49 @@ impl AutoGui {
         Ok(mat)
     }
 
+    /// Decodes a PNG keeping its alpha channel intact (if any), instead of
+    /// flattening straight to BGR like `decode_png_to_mat_color`.
+    fn decode_png_to_mat_unchanged(bytes: &[u8]) -> Result<core::Mat, Box<dyn Error>> {
+        let buf = core::Vector::<u8>::from_slice(bytes);
+        let mat = imgcodecs::imdecode(&buf, imgcodecs::IMREAD_UNCHANGED)?;
+        Ok(mat)
+    }
+
+    /// Derives an 8-bit mask (255 where alpha > 0) from a BGRA mat's alpha
+    /// channel. Returns `None` when the mat has no alpha channel.
+    fn mask_from_alpha(mat: &core::Mat) -> Result<Option<core::Mat>, Box<dyn Error>> {
+        if mat.channels() != 4 {
+            return Ok(None);
+        }
+
+        let mut channels = core::Vector::<core::Mat>::new();
+        core::split(mat, &mut channels)?;
+        let alpha = channels.get(3)?;
+
+        let mut mask = core::Mat::default();
+        core::compare(&alpha, &Scalar::all(0.0), &mut mask, core::CMP_GT)?;
+
+        Ok(Some(mask))
+    }
+
+    /// Flattens a BGRA mat down to BGR; a no-op for mats without alpha.
+    fn drop_alpha(mat: core::Mat) -> Result<core::Mat, Box<dyn Error>> {
+        if mat.channels() != 4 {
+            return Ok(mat);
+        }
+
+        let mut bgr = core::Mat::default();
+        imgproc::cvt_color(
+            &mat,
+            &mut bgr,
+            imgproc::COLOR_BGRA2BGR,
+            0,
+            core::AlgorithmHint::ALGO_HINT_DEFAULT,
+        )?;
+
+        Ok(bgr)
+    }
+
     fn clamp_rect(mut r: Rect, cols: i32, rows: i32) -> Rect {
         let x = r.x.max(0).min(cols - 1);
         let y = r.y.max(0).min(rows - 1);
@@ -122,6 +174,135 @@ impl AutoGui {
         Rect::new(x, y, w, h)
     }
 
+    /// Finds the largest 4-vertex contour in a grayscale image, i.e. the
+    /// dominant quadrilateral (e.g. a photographed screen's bezel).
+    fn find_dominant_quad(
+        gray: &core::Mat,
+    ) -> Result<Option<core::Vector<Point>>, Box<dyn Error>> {
+        let mut edges = core::Mat::default();
+        imgproc::canny(gray, &mut edges, 50.0, 150.0, 3, false)?;
+
+        let mut contours = core::Vector::<core::Vector<Point>>::new();
+        imgproc::find_contours(
+            &edges,
+            &mut contours,
+            imgproc::RETR_LIST,
+            imgproc::CHAIN_APPROX_SIMPLE,
+            Point::new(0, 0),
+        )?;
+
+        let mut best_quad = None;
+        let mut best_area = 0.0;
+
+        for contour in &contours {
+            let perimeter = imgproc::arc_length(&contour, true)?;
+            let mut approx = core::Vector::<Point>::new();
+            imgproc::approx_poly_dp(&contour, &mut approx, 0.02 * perimeter, true)?;
+
+            if approx.len() != 4 {
+                continue;
+            }
+
+            let area = imgproc::contour_area(&approx, false)?;
+            if area > best_area {
+                best_area = area;
+                best_quad = Some(approx);
+            }
+        }
+
+        Ok(best_quad)
+    }
+
+    /// Orders a quadrilateral's corners as (top-left, top-right,
+    /// bottom-right, bottom-left) using the classic coordinate-sum /
+    /// coordinate-difference trick.
+    fn order_quad_corners(points: &core::Vector<Point>) -> [core::Point2f; 4] {
+        let pts: Vec<core::Point2f> = points
+            .iter()
+            .map(|p| core::Point2f::new(p.x as f32, p.y as f32))
+            .collect();
+
+        let top_left = *pts
+            .iter()
+            .min_by(|a, b| (a.x + a.y).partial_cmp(&(b.x + b.y)).unwrap())
+            .unwrap();
+        let bottom_right = *pts
+            .iter()
+            .max_by(|a, b| (a.x + a.y).partial_cmp(&(b.x + b.y)).unwrap())
+            .unwrap();
+        let top_right = *pts
+            .iter()
+            .min_by(|a, b| (a.y - a.x).partial_cmp(&(b.y - b.x)).unwrap())
+            .unwrap();
+        let bottom_left = *pts
+            .iter()
+            .max_by(|a, b| (a.y - a.x).partial_cmp(&(b.y - b.x)).unwrap())
+            .unwrap();
+
+        [top_left, top_right, bottom_right, bottom_left]
+    }
+
+    /// Detects the dominant quadrilateral in `gray` and warps `color` to a
+    /// straightened rectangle sized to its bounding box. Returns the warped
+    /// color mat plus the inverse transform (to map matches back into
+    /// original-image coordinates), or `None` if no quadrilateral was found.
+    fn rectify_target(
+        color: &core::Mat,
+        gray: &core::Mat,
+    ) -> Result<Option<(core::Mat, core::Mat)>, Box<dyn Error>> {
+        let Some(quad) = Self::find_dominant_quad(gray)? else {
+            return Ok(None);
+        };
+
+        let corners = Self::order_quad_corners(&quad);
+
+        let bounds = imgproc::bounding_rect(&quad)?;
+        let width = bounds.width as f32;
+        let height = bounds.height as f32;
+
+        let src = core::Vector::<core::Point2f>::from_iter(corners);
+        let dst = core::Vector::<core::Point2f>::from_iter([
+            core::Point2f::new(0.0, 0.0),
+            core::Point2f::new(width, 0.0),
+            core::Point2f::new(width, height),
+            core::Point2f::new(0.0, height),
+        ]);
+
+        let transform = imgproc::get_perspective_transform(&src, &dst, core::DECOMP_LU)?;
+
+        let mut inverse_transform = core::Mat::default();
+        core::invert(&transform, &mut inverse_transform, core::DECOMP_LU)?;
+
+        let mut warped = core::Mat::default();
+        imgproc::warp_perspective(
+            color,
+            &mut warped,
+            &transform,
+            core::Size::new(width.round() as i32, height.round() as i32),
+            imgproc::INTER_LINEAR,
+            core::BORDER_CONSTANT,
+            Scalar::all(0.0),
+        )?;
+
+        Ok(Some((warped, inverse_transform)))
+    }
+
+    /// Maps a point from the rectified (warped) image back into the
+    /// original, skewed image's coordinates via the inverse transform.
+    fn unrectify_point(
+        x: u32,
+        y: u32,
+        inverse_transform: &core::Mat,
+    ) -> Result<(f32, f32), Box<dyn Error>> {
+        let src = core::Vector::<core::Point2f>::from_iter([core::Point2f::new(x as f32, y as f32)]);
+        let mut dst = core::Vector::<core::Point2f>::new();
+        core::perspective_transform(&src, &mut dst, inverse_transform)?;
+
+        let point = dst.get(0)?;
+
+        Ok((point.x.max(0.0), point.y.max(0.0)))
+    }
+
     pub fn find_image_on_screen(
         &mut self,
         assert_path: &str,
@@ -129,12 +310,16 @@ impl AutoGui {
         region: Option<FindImageRegion>,
         template_width: Option<u32>,
         result_filter: Option<FindImageResultFilter>,
+        source: ScreenSource,
+        rectify: bool,
     ) -> Result<FindImageResults, Box<dyn Error>> {
         // Load template directly as Mat and resize via OpenCV if needed
         let Some(file) = Assets::get(assert_path) else {
             return Err(format!("assets加载{}失败", assert_path).into());
         };
-        let mut template_color = Self::decode_png_to_mat_color(&file.data)?;
+        let template_unchanged = Self::decode_png_to_mat_unchanged(&file.data)?;
+        let mut template_mask = Self::mask_from_alpha(&template_unchanged)?;
+        let mut template_color = Self::drop_alpha(template_unchanged)?;
         if let Some(tw) = template_width {
             let cols = template_color.cols();
             let rows = template_color.rows();
@@ -143,19 +328,60 @@ impl AutoGui {
             let mut resized = core::Mat::default();
             imgproc::resize(&template_color, &mut resized, core::Size::new(tw as i32, new_h), 0.0, 0.0, imgproc::INTER_AREA)?;
             template_color = resized;
+
+            if let Some(mask) = &template_mask {
+                let mut resized_mask = core::Mat::default();
+                imgproc::resize(mask, &mut resized_mask, core::Size::new(tw as i32, new_h), 0.0, 0.0, imgproc::INTER_AREA)?;
+                template_mask = Some(resized_mask);
+            }
         }
 
-        // Load screenshot from embedded assets
-        let Some(screen_file) = Assets::get("screen.png") else {
-            return Err("assets加载screen.png失败".into());
+        // Load the target image either from an embedded asset or a live capture.
+        // A captured frame already honours the requested region and reports the
+        // display's scale factor, so logical-point conversion can be applied later.
+        let is_asset_source = matches!(source, ScreenSource::Asset(_));
+
+        let (mut screen_color, scale_factor) = match source {
+            ScreenSource::Asset(path) => {
+                let Some(screen_file) = Assets::get(path) else {
+                    return Err(format!("assets加载{}失败", path).into());
+                };
+                (Self::decode_png_to_mat_color(&screen_file.data)?, 1.0f32)
+            }
+            ScreenSource::Capture => {
+                let frame = if let Some((left, top, width, height)) =
+                    Self::_to_auto_gui_region(&region)
+                {
+                    Screen::capture_region(left, top, width, height)?
+                } else {
+                    Screen::capture_full()?
+                };
+                let bytes = Self::dynamic_image_to_png_bytes(&frame.image)?;
+                (Self::decode_png_to_mat_color(&bytes)?, frame.scale_factor)
+            }
         };
-        let mut screen_color = Self::decode_png_to_mat_color(&screen_file.data)?;
 
-        // Apply region crop if provided
-        if let Some((left, top, width, height)) = Self::_to_auto_gui_region(region) {
-            let rect = Rect::new(left as i32, top as i32, width as i32, height as i32);
-            let roi = core::Mat::roi(&screen_color, rect)?;
-            screen_color = roi.try_clone()?;
+        // Embedded assets aren't pre-cropped, so apply the region crop here.
+        // A captured frame is already limited to the requested region.
+        if is_asset_source {
+            if let Some((left, top, width, height)) = Self::_to_auto_gui_region(&region) {
+                let rect = Rect::new(left as i32, top as i32, width as i32, height as i32);
+                let roi = core::Mat::roi(&screen_color, rect)?;
+                screen_color = roi.try_clone()?;
+            }
+        }
+
+        // Rectify a skewed capture (e.g. a photo of a screen) before matching,
+        // so a trapezoidal target is straightened into an axis-aligned rectangle.
+        let mut inverse_transform: Option<core::Mat> = None;
+        if rectify {
+            let mut detection_gray = core::Mat::default();
+            imgproc::cvt_color(&screen_color, &mut detection_gray, imgproc::COLOR_BGR2GRAY, 0, core::AlgorithmHint::ALGO_HINT_DEFAULT)?;
+
+            if let Some((warped, inverse)) = Self::rectify_target(&screen_color, &detection_gray)? {
+                screen_color = warped;
+                inverse_transform = Some(inverse);
+            }
         }
 
         // Convert both images to grayscale and enhance contrast/noise robustness
@@ -189,15 +415,26 @@ impl AutoGui {
             return Ok(FindImageResults { width: template_proc.cols() as u32, height: template_proc.rows() as u32, list: vec![] });
         }
 
-        // Run template matching on processed images
+        // Run template matching on processed images. OpenCV's masked matching
+        // only supports TM_CCORR_NORMED, so fall back to TM_CCOEFF_NORMED
+        // when the template has no alpha channel.
         let mut result = core::Mat::default();
-        imgproc::match_template(
-            &screen_proc,
-            &template_proc,
-            &mut result,
-            imgproc::TM_CCOEFF_NORMED,
-            &core::Mat::default(),
-        )?;
+        match &template_mask {
+            Some(mask) => imgproc::match_template(
+                &screen_proc,
+                &template_proc,
+                &mut result,
+                imgproc::TM_CCORR_NORMED,
+                mask,
+            )?,
+            None => imgproc::match_template(
+                &screen_proc,
+                &template_proc,
+                &mut result,
+                imgproc::TM_CCOEFF_NORMED,
+                &core::Mat::default(),
+            )?,
+        }
         if self.debug {
             // Save the correlation map visualization
             let mut result_vis = core::Mat::default();
@@ -240,9 +477,19 @@ impl AutoGui {
                 .any(|x| result_filter.need_filter(x, candidate));
 
             if !need_filter {
+                // A rectified capture's coordinates are in the warped image;
+                // map them back to the original (skewed) image first.
+                let (original_left, original_top) = match &inverse_transform {
+                    Some(inverse) => Self::unrectify_point(left, top, inverse)?,
+                    None => (left as f32, top as f32),
+                };
+
+                // Captures report physical pixels; divide by the display scale
+                // factor so a match on a 2x/HiDPI display lands at the same
+                // logical coordinates it would on a 1x display.
                 list.push(FindImageResult {
-                    left,
-                    top,
+                    left: (original_left / scale_factor).round() as u32,
+                    top: (original_top / scale_factor).round() as u32,
                     precision: max_val as f32,
                 });
                 if self.debug {
@@ -0,0 +1,180 @@
+use image::{DynamicImage, GenericImageView};
+use opencv::{calib3d, core, features2d, prelude::*};
+use std::error::Error;
+
+/// A quadrilateral match found via ORB features + homography, reported in
+/// target-image coordinates rather than an axis-aligned `left/top`, since a
+/// rotated/perspective-skewed match isn't a rectangle.
+pub struct FeatureMatchResult {
+    /// Template corners (top-left, top-right, bottom-right, bottom-left)
+    /// projected into the target image via the estimated homography.
+    pub corners: [(f32, f32); 4],
+    pub centroid: (f32, f32),
+    pub rotation_degrees: f32,
+    pub inlier_count: usize,
+}
+
+const RATIO_TEST_THRESHOLD: f32 = 0.75;
+const MIN_GOOD_MATCHES: usize = 8;
+
+pub struct FeatureMatcher {
+    width: u32,
+    height: u32,
+    keypoints: core::Vector<core::KeyPoint>,
+    descriptors: core::Mat,
+}
+
+impl FeatureMatcher {
+    fn get_mat_from_dyn_image(image: &DynamicImage) -> Result<core::Mat, Box<dyn Error>> {
+        let gray_image = image.to_luma8();
+        let data = gray_image.as_raw();
+
+        let mat = core::Mat::from_slice(data)?
+            .reshape(1, image.height() as i32)?
+            .try_clone()?;
+
+        Ok(mat)
+    }
+
+    fn detect_and_compute(
+        mat: &core::Mat,
+    ) -> Result<(core::Vector<core::KeyPoint>, core::Mat), Box<dyn Error>> {
+        let mut orb = features2d::ORB::create(
+            500,
+            1.2,
+            8,
+            31,
+            0,
+            2,
+            features2d::ORB_ScoreType::HARRIS_SCORE,
+            31,
+            20,
+        )?;
+
+        let mut keypoints = core::Vector::<core::KeyPoint>::new();
+        let mut descriptors = core::Mat::default();
+
+        orb.detect_and_compute(
+            mat,
+            &core::no_array(),
+            &mut keypoints,
+            &mut descriptors,
+            false,
+        )?;
+
+        Ok((keypoints, descriptors))
+    }
+
+    pub fn new(template: DynamicImage) -> Result<Self, Box<dyn Error>> {
+        let (width, height) = template.dimensions();
+
+        let template_mat = Self::get_mat_from_dyn_image(&template)?;
+        let (keypoints, descriptors) = Self::detect_and_compute(&template_mat)?;
+
+        Ok(Self {
+            width,
+            height,
+            keypoints,
+            descriptors,
+        })
+    }
+
+    /// Matches the template's descriptors against a fresh target image,
+    /// filters correspondences with Lowe's ratio test, and estimates a
+    /// homography via RANSAC. Returns `None` when too few good matches (or
+    /// no stable homography) are found.
+    pub fn match_target(
+        &self,
+        target_image: DynamicImage,
+    ) -> Result<Option<FeatureMatchResult>, Box<dyn Error>> {
+        let target_mat = Self::get_mat_from_dyn_image(&target_image)?;
+        let (target_keypoints, target_descriptors) = Self::detect_and_compute(&target_mat)?;
+
+        let matcher = features2d::BFMatcher::new(core::NORM_HAMMING, false)?;
+
+        let mut knn_matches = core::Vector::<core::Vector<core::DMatch>>::new();
+        matcher.knn_train_match(
+            &self.descriptors,
+            &target_descriptors,
+            &mut knn_matches,
+            2,
+            &core::no_array(),
+            false,
+        )?;
+
+        let mut template_points = core::Vector::<core::Point2f>::new();
+        let mut target_points = core::Vector::<core::Point2f>::new();
+
+        for pair in &knn_matches {
+            if pair.len() < 2 {
+                continue;
+            }
+
+            let nearest = pair.get(0)?;
+            let second_nearest = pair.get(1)?;
+
+            if nearest.distance >= RATIO_TEST_THRESHOLD * second_nearest.distance {
+                continue;
+            }
+
+            template_points.push(self.keypoints.get(nearest.query_idx as usize)?.pt());
+            target_points.push(target_keypoints.get(nearest.train_idx as usize)?.pt());
+        }
+
+        if template_points.len() < MIN_GOOD_MATCHES {
+            return Ok(None);
+        }
+
+        let mut inlier_mask = core::Mat::default();
+        let homography = calib3d::find_homography(
+            &template_points,
+            &target_points,
+            &mut inlier_mask,
+            calib3d::RANSAC,
+            3.0,
+        )?;
+
+        if homography.empty() {
+            return Ok(None);
+        }
+
+        let template_corners = core::Vector::<core::Point2f>::from_iter([
+            core::Point2f::new(0.0, 0.0),
+            core::Point2f::new(self.width as f32, 0.0),
+            core::Point2f::new(self.width as f32, self.height as f32),
+            core::Point2f::new(0.0, self.height as f32),
+        ]);
+
+        let mut projected_corners = core::Vector::<core::Point2f>::new();
+        core::perspective_transform(&template_corners, &mut projected_corners, &homography)?;
+
+        let corners = [
+            (projected_corners.get(0)?.x, projected_corners.get(0)?.y),
+            (projected_corners.get(1)?.x, projected_corners.get(1)?.y),
+            (projected_corners.get(2)?.x, projected_corners.get(2)?.y),
+            (projected_corners.get(3)?.x, projected_corners.get(3)?.y),
+        ];
+
+        let centroid = (
+            corners.iter().map(|c| c.0).sum::<f32>() / corners.len() as f32,
+            corners.iter().map(|c| c.1).sum::<f32>() / corners.len() as f32,
+        );
+
+        // Angle of the top edge (corner 0 -> corner 1) relative to horizontal.
+        let rotation_degrees =
+            (corners[1].1 - corners[0].1).atan2(corners[1].0 - corners[0].0).to_degrees();
+
+        let inlier_count = inlier_mask
+            .data_bytes()?
+            .iter()
+            .filter(|&&byte| byte != 0)
+            .count();
+
+        Ok(Some(FeatureMatchResult {
+            corners,
+            centroid,
+            rotation_degrees,
+            inlier_count,
+        }))
+    }
+}
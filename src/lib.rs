@@ -0,0 +1,5 @@
+pub mod assets;
+pub mod auto_gui;
+pub mod feature_matcher;
+pub mod matcher;
+pub mod screen;
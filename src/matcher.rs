@@ -1,10 +1,12 @@
 use image::{DynamicImage, GenericImageView};
 use opencv::{core, imgproc, prelude::*};
+use rayon::prelude::*;
 use std::error::Error;
 use std::ops::Not;
 
 pub struct ImageMatcher {
     template: Mat,
+    mask: Option<Mat>,
     use_gray: bool,
     width: u32,
     height: u32,
@@ -52,9 +54,45 @@ impl ImageMatchRegion {
 pub struct ImageMatchResult {
     pub left: u32,
     pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub scale: f32,
     pub precision: f32,
 }
 
+pub struct ImageMatchScale {
+    start: f32,
+    end: f32,
+    step: f32,
+}
+
+impl ImageMatchScale {
+    pub fn new(start: f32, end: f32, step: f32) -> Self {
+        assert!(step > 0.0, "step must > 0");
+        assert!(end >= start, "end must >= start");
+
+        Self { start, end, step }
+    }
+
+    fn scales(&self) -> Vec<f32> {
+        // Compute the step count up front rather than accumulating `step` in
+        // a loop: repeated float addition drifts enough that the end of the
+        // range (e.g. 1.5 for 0.5..=1.5 step 0.1) can fall just past the
+        // `scale <= end + EPSILON` check and get silently dropped.
+        let steps = ((self.end - self.start) / self.step + 1e-4).floor() as i32;
+
+        (0..=steps).map(|i| self.start + i as f32 * self.step).collect()
+    }
+}
+
+/// Restricts matching to target regions whose HSV color falls within
+/// `low_hsv..=high_hsv`, so same-shaped but differently-colored elements
+/// don't produce false positives.
+pub struct ImageMatchColorGate {
+    pub low_hsv: (u8, u8, u8),
+    pub high_hsv: (u8, u8, u8),
+}
+
 pub struct ImageMatchResults {
     pub width: u32,
     pub height: u32,
@@ -84,7 +122,7 @@ impl ImageMatchFilter {
         Self { x_delta, y_delta }
     }
     fn need_filter(&self, x: u32, y: u32, result: &ImageMatchResult) -> bool {
-        if x < result.left - self.x_delta {
+        if x < result.left.saturating_sub(self.x_delta) {
             return false;
         }
 
@@ -92,7 +130,7 @@ impl ImageMatchFilter {
             return false;
         }
 
-        if y < result.top - self.y_delta {
+        if y < result.top.saturating_sub(self.y_delta) {
             return false;
         }
 
@@ -153,10 +191,33 @@ impl ImageMatcher {
         Ok(gray_mat)
     }
 
-    pub fn new(
+    /// Derives an 8-bit mask (255 where alpha > 0, 0 elsewhere) from a
+    /// template's alpha channel, so transparent/cut-out pixels don't
+    /// pollute the correlation score. Returns `None` for opaque templates.
+    fn mask_from_alpha(image: &DynamicImage) -> Result<Option<Mat>, Box<dyn Error>> {
+        if image.color().has_alpha().not() {
+            return Ok(None);
+        }
+
+        let rgba_image = image.to_rgba8();
+        let alpha: Vec<u8> = rgba_image.pixels().map(|pixel| pixel[3]).collect();
+
+        let alpha_mat = Mat::from_slice(&alpha)?
+            .reshape(1, image.height() as i32)?
+            .try_clone()?;
+
+        let mut mask = Mat::default();
+
+        core::compare(&alpha_mat, &core::Scalar::all(0.0), &mut mask, core::CMP_GT)?;
+
+        Ok(Some(mask))
+    }
+
+    fn build(
         template: DynamicImage,
         use_gray: bool,
         width: Option<u32>,
+        mask: Option<Mat>,
     ) -> Result<Self, Box<dyn Error>> {
         let (mut w, mut h) = *&template.dimensions();
 
@@ -169,8 +230,14 @@ impl ImageMatcher {
         let mat = Self::resize_mat(mat, width)?;
         let mat = Self::gray_mat(mat, use_gray)?;
 
+        let mask = match mask {
+            Some(mask) => Some(Self::resize_mat(mask, width)?),
+            None => None,
+        };
+
         let matcher = Self {
             template: mat,
+            mask,
             use_gray,
             width: w,
             height: h,
@@ -178,6 +245,58 @@ impl ImageMatcher {
 
         Ok(matcher)
     }
+
+    pub fn new(
+        template: DynamicImage,
+        use_gray: bool,
+        width: Option<u32>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mask = Self::mask_from_alpha(&template)?;
+
+        Self::build(template, use_gray, width, mask)
+    }
+
+    /// Like `new`, but takes an explicit mask instead of deriving one from
+    /// the template's alpha channel (useful when the caller already has a
+    /// mask, or the template PNG has no alpha).
+    pub fn with_mask(
+        template: DynamicImage,
+        use_gray: bool,
+        width: Option<u32>,
+        mask: Mat,
+    ) -> Result<Self, Box<dyn Error>> {
+        Self::build(template, use_gray, width, Some(mask))
+    }
+
+    fn scaled_mat(mat: &Mat, scale: f32) -> Result<Mat, Box<dyn Error + Send + Sync>> {
+        if (scale - 1.0).abs() < f32::EPSILON {
+            return Ok(mat.try_clone()?);
+        }
+
+        let mut scaled_mat = Mat::default();
+
+        imgproc::resize(
+            mat,
+            &mut scaled_mat,
+            core::Size::new(0, 0),
+            scale as f64,
+            scale as f64,
+            imgproc::INTER_LANCZOS4,
+        )?;
+
+        Ok(scaled_mat)
+    }
+
+    fn scale_template(&self, scale: f32) -> Result<Mat, Box<dyn Error + Send + Sync>> {
+        Self::scaled_mat(&self.template, scale)
+    }
+
+    fn scale_mask(&self, scale: f32) -> Result<Option<Mat>, Box<dyn Error + Send + Sync>> {
+        match &self.mask {
+            Some(mask) => Ok(Some(Self::scaled_mat(mask, scale)?)),
+            None => Ok(None),
+        }
+    }
 }
 
 impl ImageMatcher {
@@ -197,6 +316,52 @@ impl ImageMatcher {
 
         Ok(cropped_mat)
     }
+
+    /// Zeroes out every target pixel whose HSV color falls outside the
+    /// gate's bounds, so matches can only form where the template's
+    /// dominant color actually appears.
+    fn apply_color_gate(mat: Mat, gate: &ImageMatchColorGate) -> Result<Mat, Box<dyn Error>> {
+        let mut hsv_mat = Mat::default();
+
+        // `mat` comes from `get_mat_from_dyn_image`, which reshapes
+        // `to_rgb8()` bytes straight into the Mat, so channel 0 is Red, not
+        // Blue. Use the RGB variant here instead of BGR2HSV, or this silently
+        // swaps red and blue hues.
+        imgproc::cvt_color(
+            &mat,
+            &mut hsv_mat,
+            imgproc::COLOR_RGB2HSV,
+            0,
+            core::AlgorithmHint::ALGO_HINT_DEFAULT,
+        )?;
+
+        let low = core::Scalar::new(
+            gate.low_hsv.0 as f64,
+            gate.low_hsv.1 as f64,
+            gate.low_hsv.2 as f64,
+            0.0,
+        );
+        let high = core::Scalar::new(
+            gate.high_hsv.0 as f64,
+            gate.high_hsv.1 as f64,
+            gate.high_hsv.2 as f64,
+            0.0,
+        );
+
+        // `core::compare` on a 3-channel Mat compares each channel
+        // independently, so it can't be used to AND H, S and V together.
+        // `in_range` performs that combined per-pixel test directly,
+        // producing a single-channel mask that either keeps or zeroes a
+        // whole pixel.
+        let mut gate_mask = Mat::default();
+        core::in_range(&hsv_mat, &low, &high, &mut gate_mask)?;
+
+        let mut gated_mat = Mat::zeros(mat.rows(), mat.cols(), mat.typ())?.to_mat()?;
+        mat.copy_to_masked(&mut gated_mat, &gate_mask)?;
+
+        Ok(gated_mat)
+    }
+
     fn need_filter(
         x: u32,
         y: u32,
@@ -212,12 +377,113 @@ impl ImageMatcher {
         false
     }
 
+    fn clamp_rect(rect: core::Rect, cols: i32, rows: i32) -> core::Rect {
+        let x = rect.x.max(0).min(cols - 1);
+        let y = rect.y.max(0).min(rows - 1);
+        let width = rect.width.max(1).min(cols - x);
+        let height = rect.height.max(1).min(rows - y);
+
+        core::Rect::new(x, y, width, height)
+    }
+
+    /// Matches a single scale with the iterative `min_max_loc` +
+    /// rectangle-suppression loop: repeatedly take the global max, stop once
+    /// it drops below `precision`, then zero a suppression rectangle (template
+    /// size padded by the filter deltas) before looking for the next peak.
+    /// This is far cheaper than scanning every cell of the correlation map.
+    fn match_scale(
+        &self,
+        target_mat: &Mat,
+        scale: f32,
+        precision: f32,
+        filter: &ImageMatchFilter,
+    ) -> Result<Vec<ImageMatchResult>, Box<dyn Error + Send + Sync>> {
+        let template = self.scale_template(scale)?;
+        let mask = self.scale_mask(scale)?;
+        let template_size = template.size().unwrap_or_default();
+
+        if template_size.width > target_mat.cols() || template_size.height > target_mat.rows() {
+            return Ok(Vec::new());
+        }
+
+        let mut result_mat = Mat::default();
+
+        // OpenCV's masked matching only supports TM_CCORR_NORMED.
+        match &mask {
+            Some(mask) => imgproc::match_template(
+                target_mat,
+                &template,
+                &mut result_mat,
+                imgproc::TM_CCORR_NORMED,
+                mask,
+            )?,
+            None => imgproc::match_template(
+                target_mat,
+                &template,
+                &mut result_mat,
+                imgproc::TM_CCOEFF_NORMED,
+                &core::no_array(),
+            )?,
+        }
+
+        let mut hits = Vec::new();
+
+        loop {
+            let mut max_val = 0.0f64;
+            let mut max_loc = core::Point::new(0, 0);
+
+            core::min_max_loc(
+                &result_mat,
+                None,
+                Some(&mut max_val),
+                None,
+                Some(&mut max_loc),
+                &core::no_array(),
+            )?;
+
+            if (max_val as f32) < precision {
+                break;
+            }
+
+            hits.push(ImageMatchResult {
+                left: max_loc.x.max(0) as u32,
+                top: max_loc.y.max(0) as u32,
+                width: template_size.width as u32,
+                height: template_size.height as u32,
+                scale,
+                precision: max_val as f32,
+            });
+
+            let suppress_rect = core::Rect::new(
+                max_loc.x - filter.x_delta as i32,
+                max_loc.y - filter.y_delta as i32,
+                template_size.width + filter.x_delta as i32 * 2,
+                template_size.height + filter.y_delta as i32 * 2,
+            );
+            let suppress_rect =
+                Self::clamp_rect(suppress_rect, result_mat.cols(), result_mat.rows());
+
+            imgproc::rectangle(
+                &mut result_mat,
+                suppress_rect,
+                core::Scalar::all(0.0),
+                -1,
+                imgproc::LINE_8,
+                0,
+            )?;
+        }
+
+        Ok(hits)
+    }
+
     pub fn start_matching(
         &self,
         target_image: DynamicImage,
         precision: f32,
         region: Option<ImageMatchRegion>,
         filter: Option<ImageMatchFilter>,
+        scale: Option<ImageMatchScale>,
+        color_gate: Option<ImageMatchColorGate>,
     ) -> Result<ImageMatchResults, Box<dyn Error>> {
         let (mut width, mut height) = target_image.dimensions();
 
@@ -228,56 +494,45 @@ impl ImageMatcher {
 
         let target_mat = Self::get_mat_from_dyn_image(target_image)?;
         let target_mat = Self::crop_mat(target_mat, region)?;
-        let target_mat = Self::gray_mat(target_mat, self.use_gray)?;
-
-        let mut result_mat = Mat::default();
 
-        imgproc::match_template(
-            &target_mat,
-            &self.template,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &core::no_array(),
-        )?;
+        let target_mat = match &color_gate {
+            Some(gate) => Self::apply_color_gate(target_mat, gate)?,
+            None => target_mat,
+        };
 
-        let mut results = Vec::new();
+        let target_mat = Self::gray_mat(target_mat, self.use_gray)?;
 
-        // 遍历结果矩阵，找到所有超过阈值的匹配点
-        let result_size = result_mat.size().unwrap_or_default();
+        let scales = scale.map(|scale| scale.scales()).unwrap_or_else(|| vec![1.0]);
 
         let filter = filter.unwrap_or_else(|| ImageMatchFilter::new(5, 5));
 
-        for y in 0..result_size.height as u32 {
-            for x in 0..result_size.width as u32 {
-                if Self::need_filter(x, y, &results, &filter) {
-                    continue;
-                }
-
-                let Ok(threshold) = result_mat.at_2d::<f32>(y as i32, x as i32) else {
-                    continue;
-                };
-
-                let threshold = *threshold;
-
-                if threshold < precision {
-                    continue;
-                }
-
-                results.push(ImageMatchResult {
-                    left: x,
-                    top: y,
-                    precision: threshold,
-                });
-            }
-        }
-
-        // 按精度降序排序
-        results.sort_by(|a, b| {
+        // 各尺度的匹配互不依赖，交给 rayon 并行跑，充分利用多核
+        let mut candidates: Vec<ImageMatchResult> = scales
+            .par_iter()
+            .map(|&scale| self.match_scale(&target_mat, scale, precision, &filter))
+            .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()
+            .map_err(|err| -> Box<dyn Error> { err })?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // 按精度降序排序，保证同一位置相邻尺度的重复命中优先保留精度最高的
+        candidates.sort_by(|a, b| {
             b.precision
                 .partial_cmp(&a.precision)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        let mut results = Vec::new();
+
+        for candidate in candidates {
+            if Self::need_filter(candidate.left, candidate.top, &results, &filter) {
+                continue;
+            }
+
+            results.push(candidate);
+        }
+
         Ok(ImageMatchResults::new(width, height, results))
     }
 }
@@ -0,0 +1,77 @@
+use image::{DynamicImage, GenericImageView};
+use std::error::Error;
+use xcap::Monitor;
+
+/// A captured frame plus the OS-reported scale factor (e.g. 2.0 on a
+/// retina/HiDPI display), so callers can convert matched pixel
+/// coordinates back into logical points.
+pub struct CapturedFrame {
+    pub image: DynamicImage,
+    pub scale_factor: f32,
+}
+
+pub struct Screen;
+
+impl Screen {
+    fn primary_monitor() -> Result<Monitor, Box<dyn Error>> {
+        let monitor = Monitor::from_point(0, 0)?;
+
+        Ok(monitor)
+    }
+
+    pub fn capture_full() -> Result<CapturedFrame, Box<dyn Error>> {
+        let monitor = Self::primary_monitor()?;
+        let scale_factor = monitor.scale_factor()?;
+        let buffer = monitor.capture_image()?;
+
+        Ok(CapturedFrame {
+            image: DynamicImage::ImageRgba8(buffer),
+            scale_factor,
+        })
+    }
+
+    /// `left`/`top`/`width`/`height` are logical points, i.e. the same
+    /// coordinate system `scale_factor` converts matched pixels back into.
+    /// `capture_full` returns a physical-pixel buffer, so the region is
+    /// scaled up before cropping; skipping that would crop the wrong area
+    /// on any HiDPI display where `scale_factor != 1.0`.
+    pub fn capture_region(
+        left: u32,
+        top: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<CapturedFrame, Box<dyn Error>> {
+        let frame = Self::capture_full()?;
+        let scale_factor = frame.scale_factor;
+
+        let left = (left as f32 * scale_factor).round() as u32;
+        let top = (top as f32 * scale_factor).round() as u32;
+        let width = (width as f32 * scale_factor).round() as u32;
+        let height = (height as f32 * scale_factor).round() as u32;
+
+        let (frame_width, frame_height) = frame.image.dimensions();
+
+        // Independent per-field rounding can push left + width (or top +
+        // height) a pixel past the captured frame's actual size even when
+        // the logical region was fully on-screen (e.g. fractional scale
+        // factors like 1.5x/1.25x). `GenericImageView::view` panics rather
+        // than erroring on an out-of-bounds rect, so validate first.
+        if left >= frame_width
+            || top >= frame_height
+            || left + width > frame_width
+            || top + height > frame_height
+        {
+            return Err(format!(
+                "capture_region out of bounds: scaled rect ({left}, {top}, {width}, {height}) exceeds captured frame {frame_width}x{frame_height}"
+            )
+            .into());
+        }
+
+        let cropped = frame.image.view(left, top, width, height).to_image();
+
+        Ok(CapturedFrame {
+            image: DynamicImage::ImageRgba8(cropped),
+            scale_factor,
+        })
+    }
+}